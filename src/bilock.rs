@@ -0,0 +1,158 @@
+//SPDX-License-Identifier: MIT OR Apache-2.0
+
+/*! Provides a lock specialized for exactly two owners of one resource.
+
+Where [`crate::AtomicLockAsync`] supports an arbitrary number of waiters via a wakelist,
+`BiLock` assumes exactly two participants (for example, the read and write halves of a
+split connection) and so only ever needs a single waker slot, making the uncontended
+acquire path a single CAS.
+ */
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use atomic_waker::AtomicWaker;
+
+#[derive(Debug)]
+struct Inner<T> {
+    locked: AtomicBool,
+    waker: AtomicWaker,
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for Inner<T> {}
+unsafe impl<T: Send> Sync for Inner<T> {}
+
+/**
+One of two handles to a single shared value, each able to lock it independently.
+
+Created in pairs via [`BiLock::new`]; the two handles share an allocation but have no
+other connection to each other, so (unlike [`crate::AtomicLockAsync`]'s wakelist) only
+one waker slot is ever needed.
+*/
+#[derive(Debug)]
+pub struct BiLock<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> BiLock<T> {
+    /**
+    Creates a new value, returning two handles that each lock it independently.
+*/
+    pub fn new(t: T) -> (BiLock<T>, BiLock<T>) {
+        let inner = Arc::new(Inner {
+            locked: AtomicBool::new(false),
+            waker: AtomicWaker::new(),
+            value: UnsafeCell::new(t),
+        });
+        (BiLock { inner: inner.clone() }, BiLock { inner })
+    }
+
+    /**
+    Attempts to acquire the lock, registering `cx`'s waker to be woken on failure.
+*/
+    pub fn poll_lock(&self, cx: &mut std::task::Context<'_>) -> Poll<BiLockGuard<'_, T>> {
+        if self.try_acquire() {
+            return Poll::Ready(BiLockGuard { lock: self });
+        }
+        self.inner.waker.register(cx.waker());
+        //check again in case the other handle released between our first attempt and
+        //registering the waker.
+        if self.try_acquire() {
+            Poll::Ready(BiLockGuard { lock: self })
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn try_acquire(&self) -> bool {
+        self.inner.locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok()
+    }
+
+    /**
+    Locks this handle's value, waiting for the other handle to release it if necessary.
+*/
+    pub fn lock(&self) -> BiLockFuture<'_, T> {
+        BiLockFuture { lock: self }
+    }
+
+    /**
+    Recombines two handles into the value they share, if they in fact originate from the
+    same [`BiLock::new`] call.
+*/
+    pub fn reunite(self, other: Self) -> Result<T, ReuniteError<T>> where T: Unpin {
+        if Arc::ptr_eq(&self.inner, &other.inner) {
+            drop(other);
+            let inner = Arc::try_unwrap(self.inner)
+                .unwrap_or_else(|_| panic!("BiLock::reunite: more than two handles to the same value"));
+            Ok(inner.value.into_inner())
+        } else {
+            Err(ReuniteError(self, other))
+        }
+    }
+}
+
+/**
+A guard providing exclusive access to the value inside a [`BiLock`].
+*/
+#[derive(Debug)]
+pub struct BiLockGuard<'a, T> {
+    lock: &'a BiLock<T>,
+}
+
+impl<T> Drop for BiLockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.inner.locked.store(false, Ordering::Release);
+        self.lock.inner.waker.wake();
+    }
+}
+
+impl<T> std::ops::Deref for BiLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.lock.inner.value.get() }
+    }
+}
+
+impl<T> std::ops::DerefMut for BiLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.lock.inner.value.get() }
+    }
+}
+
+/**
+A future, produced by [`BiLock::lock`], which resolves to a [`BiLockGuard`].
+*/
+#[derive(Debug)]
+#[must_use]
+pub struct BiLockFuture<'a, T> {
+    lock: &'a BiLock<T>,
+}
+
+impl<'a, T> std::future::Future for BiLockFuture<'a, T> {
+    type Output = BiLockGuard<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        self.lock.poll_lock(cx)
+    }
+}
+
+/**
+The error returned by [`BiLock::reunite`] when the two handles don't share an allocation.
+
+The original handles are returned so the caller can recover them.
+*/
+#[derive(Debug)]
+pub struct ReuniteError<T>(pub BiLock<T>, pub BiLock<T>);
+
+impl<T> fmt::Display for ReuniteError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tried to reunite two BiLocks that don't originate from the same BiLock::new call")
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for ReuniteError<T> {}
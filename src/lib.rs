@@ -7,19 +7,139 @@ Where a mutex would block, we yield execution.
 This can be considered an async version of [`atomiclock`](https://sealedabstract.com/code/atomiclock).
  */
 
+use std::collections::VecDeque;
+use std::fmt;
 use std::mem::ManuallyDrop;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::task::Poll;
+use std::time::{Duration, Instant};
 use atomic_waker::AtomicWaker;
 use logwise::perfwarn_begin;
+// `futures-io` is an optional feature gating the `AsyncRead`/`AsyncWrite` passthrough impls
+// below; enabling it requires an optional `futures` dependency (`features = ["futures-io"]`
+// in the consuming crate mapping to `futures = { version = "0.3", optional = true }` and
+// `futures-io = ["dep:futures"]` in this crate's manifest).
+#[cfg(feature = "futures-io")]
+use futures::io::{AsyncRead, AsyncWrite};
+
+mod rwlock;
+pub use rwlock::{RwLock, RwLockReadGuard, RwLockReadFuture, RwLockReadWarnFuture, RwLockWriteGuard, RwLockWriteFuture, RwLockWriteWarnFuture};
+
+mod bilock;
+pub use bilock::{BiLock, BiLockGuard, BiLockFuture, ReuniteError};
+
+/**
+A guard wrapped in this error indicates that the underlying lock was poisoned: some
+task panicked while the guard it held was still alive (including panicking during
+cancellation of a future while the guard was live).
+
+The guard is still returned, mirroring [`std::sync::PoisonError`], so callers can
+recover the (possibly inconsistent) data if they choose to.
+*/
+pub struct PoisonError<G> {
+    guard: G,
+}
+
+impl<G> PoisonError<G> {
+    /**
+    Creates a new `PoisonError` wrapping the given guard.
+*/
+    pub fn new(guard: G) -> Self {
+        PoisonError { guard }
+    }
+
+    /**
+    Consumes this error, returning the underlying guard.
+*/
+    pub fn into_inner(self) -> G {
+        self.guard
+    }
+
+    /**
+    Returns a reference to the underlying guard.
+*/
+    pub fn get_ref(&self) -> &G {
+        &self.guard
+    }
+
+    /**
+    Returns a mutable reference to the underlying guard.
+*/
+    pub fn get_mut(&mut self) -> &mut G {
+        &mut self.guard
+    }
+}
+
+impl<G> fmt::Debug for PoisonError<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoisonError").finish_non_exhaustive()
+    }
+}
+
+impl<G> fmt::Display for PoisonError<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "poisoned lock: another task failed inside")
+    }
+}
+
+impl<G> std::error::Error for PoisonError<G> {}
+
+/**
+The result of locking, which may indicate the lock was poisoned.
+
+See [`PoisonError`] for details.
+*/
+pub type LockResult<G> = Result<G, PoisonError<G>>;
+
+/**
+Once the head of the wakelist has been waiting longer than this, `Guard::drop` switches
+to a fair handoff: the head is marked [`WaitNode::notified`] and new pollers are required
+to queue behind it rather than racing it for the underlying lock.
+*/
+const FAIRNESS_THRESHOLD: Duration = Duration::from_micros(500);
+
+/**
+An entry in the FIFO wakelist.
+
+Each waiter gets its own node so `Guard::drop` can wake precisely the head of the
+queue (rather than every parked waiter) and so a cancelled [`LockFuture`] can remove
+just its own entry.
+*/
+#[derive(Debug)]
+struct WaitNode {
+    waker: AtomicWaker,
+    notified: AtomicBool,
+    queued_at: Instant,
+}
 
 #[derive(Debug)]
 pub struct AtomicLockAsync<T> {
     lock: atomiclock::AtomicLock<T>,
-    wakelist: atomiclock_spinlock::Lock<Vec<Arc<AtomicWaker>>>,
+    wakelist: atomiclock_spinlock::Lock<VecDeque<Arc<WaitNode>>>,
+    poisoned: AtomicBool,
+    /**
+    Wait-node for whichever [`AsyncRead`] poll is currently parked, if any.
+
+    Only present under `futures-io`; see the `AsyncRead`/`AsyncWrite` passthrough impls
+    near the bottom of this file.
+    */
+    #[cfg(feature = "futures-io")]
+    io_read: atomiclock_spinlock::Lock<Option<Arc<WaitNode>>>,
+    /**
+    Same as `io_read`, but for the [`AsyncWrite`] half (`poll_write`/`poll_flush`/`poll_close`).
+    */
+    #[cfg(feature = "futures-io")]
+    io_write: atomiclock_spinlock::Lock<Option<Arc<WaitNode>>>,
 }
 
+//None of `AtomicLockAsync`'s fields are self-referential, so pinning it gives no guarantee
+//we rely on; the `AsyncRead`/`AsyncWrite` impls below need `Self: Unpin` to get a plain
+//`&mut Self` out of the `Pin<&mut Self>` the traits are polled through.
+#[cfg(feature = "futures-io")]
+impl<T> Unpin for AtomicLockAsync<T> {}
+
 
 #[derive(Debug)]
 pub struct Guard<'a, T> {
@@ -32,7 +152,27 @@ pub struct Guard<'a, T> {
 #[must_use]
 pub struct LockFuture<'a, T> {
     lock: &'a AtomicLockAsync<T>,
-    registered_waker: Option<Arc<AtomicWaker>>,
+    node: Option<Arc<WaitNode>>,
+}
+
+/**
+Like [`Guard`], but owns an `Arc` to the lock rather than borrowing it, so it can be
+moved into a `'static` future or a spawned task. Produced by [`AtomicLockAsync::lock_owned`].
+*/
+#[derive(Debug)]
+pub struct OwnedGuard<T: 'static> {
+    _guard: ManuallyDrop<atomiclock::Guard<'static, T>>,
+    lock: Arc<AtomicLockAsync<T>>,
+}
+
+/**
+A future returned by [`AtomicLockAsync::lock_owned`], resolving to an [`OwnedGuard`].
+*/
+#[derive(Debug)]
+#[must_use]
+pub struct OwnedLockFuture<T: 'static> {
+    lock: Arc<AtomicLockAsync<T>>,
+    node: Option<Arc<WaitNode>>,
 }
 
 
@@ -43,24 +183,151 @@ impl<T> AtomicLockAsync<T> {
     pub const fn new(t: T) -> Self {
         AtomicLockAsync {
             lock: atomiclock::AtomicLock::new(t),
-            wakelist: atomiclock_spinlock::Lock::new(vec![])
+            wakelist: atomiclock_spinlock::Lock::new(VecDeque::new()),
+            poisoned: AtomicBool::new(false),
+            #[cfg(feature = "futures-io")]
+            io_read: atomiclock_spinlock::Lock::new(None),
+            #[cfg(feature = "futures-io")]
+            io_write: atomiclock_spinlock::Lock::new(None),
+        }
+    }
+
+    /**
+    Wraps a freshly-acquired guard in a [`LockResult`], consulting the poison flag at the
+    moment of acquisition.
+*/
+    fn finish_lock<'a>(&'a self, guard: atomiclock::Guard<'a, T>) -> LockResult<Guard<'a, T>> {
+        let guard = Guard { _guard: ManuallyDrop::new(guard), lock: self };
+        if self.poisoned.load(Ordering::Acquire) {
+            Err(PoisonError::new(guard))
+        } else {
+            Ok(guard)
+        }
+    }
+
+    /**
+    Core FIFO/fairness acquire logic shared by [`LockFuture`] and [`OwnedLockFuture`].
+
+    `node` is the caller's queue-entry slot; on `Pending` it may be populated with a
+    freshly-parked [`WaitNode`].
+    */
+    fn poll_acquire<'h>(&'h self, node: &mut Option<Arc<WaitNode>>, cx: &mut std::task::Context<'_>) -> Poll<atomiclock::Guard<'h, T>> {
+        if let Some(n) = node.as_ref() {
+            n.waker.register(cx.waker());
+            //if another waiter at the head of the queue has a fair handoff pending,
+            //don't race it for the lock; just wait to be woken.
+            if !n.notified.load(Ordering::Acquire) {
+                let fair_handoff_pending = self.wakelist.spin_lock_warn().front()
+                    .is_some_and(|head| !Arc::ptr_eq(head, n) && head.notified.load(Ordering::Acquire));
+                if fair_handoff_pending {
+                    return Poll::Pending;
+                }
+            }
+            return match self.lock.lock() {
+                Some(guard) => {
+                    let n = node.take().unwrap();
+                    self.wakelist.spin_lock_warn().retain(|x| !Arc::ptr_eq(x, &n));
+                    Poll::Ready(guard)
+                },
+                None => Poll::Pending,
+            };
         }
+        //opportunistic fast path, unless a fair handoff is already in progress
+        //for the current head of the queue.
+        let fair_handoff_pending = self.wakelist.spin_lock_warn().front()
+            .is_some_and(|head| head.notified.load(Ordering::Acquire));
+        if !fair_handoff_pending {
+            if let Some(guard) = self.lock.lock() {
+                return Poll::Ready(guard);
+            }
+        }
+        let n = Arc::new(WaitNode{
+            waker: AtomicWaker::new(),
+            notified: AtomicBool::new(false),
+            queued_at: Instant::now(),
+        });
+        n.waker.register(cx.waker());
+        self.wakelist.spin_lock_warn().push_back(n.clone());
+        *node = Some(n);
+        Poll::Pending
     }
 
+    /**
+    Like [`poll_acquire`](Self::poll_acquire), but for the `AsyncRead`/`AsyncWrite`
+    passthrough impls: the wait-node lives in `slot` (one of `self`'s `io_read`/`io_write`
+    fields) rather than in a caller-owned future, since `poll_read`/`poll_write` etc. take
+    `&self`/`Pin<&mut Self>` rather than owning a dedicated future of their own.
+    */
+    #[cfg(feature = "futures-io")]
+    fn poll_io_lock<'s>(&'s self, slot: &atomiclock_spinlock::Lock<Option<Arc<WaitNode>>>, cx: &mut std::task::Context<'_>) -> Poll<LockResult<Guard<'s, T>>> {
+        let mut node = slot.spin_lock_warn();
+        self.poll_acquire(&mut node, cx).map(|guard| self.finish_lock(guard))
+    }
+
+    /**
+    Wakes the head of `queue`, marking it for a fair handoff if it's been waiting long
+    enough.
+    */
+    fn wake_front(queue: &VecDeque<Arc<WaitNode>>) {
+        if let Some(head) = queue.front() {
+            if head.queued_at.elapsed() > FAIRNESS_THRESHOLD {
+                head.notified.store(true, Ordering::Release);
+            }
+            head.waker.wake();
+        }
+    }
+
+    /**
+    Removes `node`'s queue entry, if any. Called when a [`LockFuture`]/[`OwnedLockFuture`]
+    is dropped before acquiring, so a cancelled waiter can't permanently hold a fair
+    handoff or leave a stale waker behind.
+
+    If the removed entry was the head of the queue, its successor is woken here too: `release`
+    only ever wakes the *current* head, so a waiter that's notified (or about to be) and then
+    cancelled before re-polling would otherwise strand everyone behind it forever, since the
+    lock may already be free with nothing left to call `release` and pass the wakeup along.
+    */
+    fn deregister(&self, node: &mut Option<Arc<WaitNode>>) {
+        if let Some(n) = node.take() {
+            let mut queue = self.wakelist.spin_lock_warn();
+            let was_front = queue.front().is_some_and(|head| Arc::ptr_eq(head, &n));
+            queue.retain(|x| !Arc::ptr_eq(x, &n));
+            if was_front {
+                Self::wake_front(&queue);
+            }
+        }
+    }
+
+    /**
+    Wakes the head of the queue, marking it for a fair handoff if it's been waiting long
+    enough. Called from `Guard`/`OwnedGuard` drop, after the underlying lock is released.
+    */
+    fn release(&self) {
+        Self::wake_front(&self.wakelist.spin_lock_warn());
+    }
+
+    /**
+    Poisons the lock if the current thread is unwinding from a panic. Called from
+    `Guard`/`OwnedGuard` drop, before the underlying lock is released.
+    */
+    fn mark_poisoned_if_panicking(&self) {
+        if std::thread::panicking() {
+            self.poisoned.store(true, Ordering::Release);
+        }
+    }
 
     /**
     Locks the lock if it is available, returning a guard if it is.
 */
-    pub fn lock_if_available(&self) -> Option<Guard<'_, T>> {
-        self.lock.lock()
-            .map(|guard| Guard { _guard: ManuallyDrop::new(guard), lock: self })
+    pub fn lock_if_available(&self) -> Option<LockResult<Guard<'_, T>>> {
+        self.lock.lock().map(|guard| self.finish_lock(guard))
     }
 
     /**
     Locks the lock.
 */
     pub fn lock(&self) -> LockFuture<T> {
-        LockFuture{ lock: self, registered_waker: None }
+        LockFuture{ lock: self, node: None }
     }
 
     /**
@@ -78,19 +345,62 @@ impl<T> AtomicLockAsync<T> {
     pub fn into_inner(self) -> T {
         self.lock.into_inner()
     }
+
+    /**
+    Returns whether the lock is currently poisoned.
+
+    See [`PoisonError`] for how a lock becomes poisoned.
+*/
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Acquire)
+    }
+
+    /**
+    Clears the poison flag, allowing future lock acquisitions to succeed normally.
+
+    This does not repair any broken invariant in `T`; it only tells the lock to stop
+    reporting [`PoisonError`].
+*/
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Release);
+    }
+}
+
+impl<T: 'static> AtomicLockAsync<T> {
+    /**
+    Locks the lock, returning a guard whose lifetime is not tied to a borrow of `self`.
+
+    Unlike [`lock`](Self::lock), the resulting [`OwnedGuard`] holds its own `Arc` to the
+    lock, so it (and the future that produces it) can be moved into a `'static` future or
+    a spawned task.
+    */
+    pub fn lock_owned(self: &Arc<Self>) -> OwnedLockFuture<T> {
+        OwnedLockFuture { lock: self.clone(), node: None }
+    }
+
+    /**
+    Wraps a freshly-acquired guard in an owned [`LockResult`], consulting the poison flag
+    at the moment of acquisition.
+*/
+    fn finish_lock_owned(self_arc: &Arc<Self>, guard: atomiclock::Guard<'_, T>) -> LockResult<OwnedGuard<T>> {
+        //Safety: `guard` borrows `self_arc`'s inner `atomiclock::AtomicLock<T>`. We keep
+        //`self_arc` alive inside `OwnedGuard` for at least as long as `guard` is alive,
+        //which is exactly what the borrow this lifetime extension stands in for requires.
+        let guard: atomiclock::Guard<'static, T> = unsafe { std::mem::transmute(guard) };
+        let guard = OwnedGuard { _guard: ManuallyDrop::new(guard), lock: self_arc.clone() };
+        if self_arc.poisoned.load(Ordering::Acquire) {
+            Err(PoisonError::new(guard))
+        } else {
+            Ok(guard)
+        }
+    }
 }
 
 impl<T> Drop for Guard<'_, T> {
     fn drop(&mut self) {
+        self.lock.mark_poisoned_if_panicking();
         unsafe{ManuallyDrop::drop(&mut self._guard)}; //release the underlying lock first
-        //then wake a task.
-        {
-            let mut lock = self.lock.wakelist.spin_lock_warn();
-            for drain in lock.drain(..) {
-                drain.wake();
-            }
-        }
-
+        self.lock.release(); //then wake (only) the head of the queue, in FIFO order.
     }
 }
 
@@ -103,31 +413,81 @@ impl<T> Guard<'_, T> {
     }
 }
 
+impl<T> Drop for LockFuture<'_, T> {
+    fn drop(&mut self) {
+        //if we were cancelled before acquiring, remove our entry so we don't permanently
+        //hold a fair handoff (or leave a stale waker in the queue).
+        self.lock.deregister(&mut self.node);
+    }
+}
+
 impl<'a, T> std::future::Future for LockFuture<'a, T> {
-    type Output = Guard<'a, T>;
-
-    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
-        match self.lock.lock.lock() {
-            Some(guard) => {
-                std::task::Poll::Ready(Guard{_guard: ManuallyDrop::new(guard), lock: self.lock})
-            },
-            None => {
-                match self.registered_waker {
-                    Some(ref waker) => {
-                        waker.register(cx.waker());
-                        Poll::Pending
-                    },
-                    None => {
-                        let waker = Arc::new(AtomicWaker::new());
-                        waker.register(cx.waker());
-                        self.lock.wakelist.spin_lock_warn().push(waker.clone());
-                        self.registered_waker = Some(waker);
-
-                        Poll::Pending
-                    }
-                }
-            }
-        }
+    type Output = LockResult<Guard<'a, T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
+        let this = self.get_mut();
+        this.lock.poll_acquire(&mut this.node, cx).map(|guard| this.lock.finish_lock(guard))
+    }
+}
+
+impl<T: 'static> Drop for OwnedGuard<T> {
+    fn drop(&mut self) {
+        self.lock.mark_poisoned_if_panicking();
+        unsafe{ManuallyDrop::drop(&mut self._guard)}; //release the underlying lock first
+        self.lock.release(); //then wake (only) the head of the queue, in FIFO order.
+    }
+}
+
+impl<T: 'static> OwnedGuard<T> {
+    /**
+    Accesses the underlying lock.
+*/
+    pub fn lock(&self) -> Arc<AtomicLockAsync<T>> {
+        self.lock.clone()
+    }
+}
+
+impl<T: 'static> AsRef<T> for OwnedGuard<T> {
+    fn as_ref(&self) -> &T {
+        self._guard.as_ref()
+    }
+}
+
+impl<T: 'static> AsMut<T> for OwnedGuard<T> {
+    fn as_mut(&mut self) -> &mut T {
+        self._guard.as_mut()
+    }
+}
+
+impl<T: 'static> std::ops::Deref for OwnedGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self._guard.deref()
+    }
+}
+
+impl<T: 'static> std::ops::DerefMut for OwnedGuard<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self._guard.deref_mut()
+    }
+}
+
+impl<T: 'static> Drop for OwnedLockFuture<T> {
+    fn drop(&mut self) {
+        //if we were cancelled before acquiring, remove our entry so we don't permanently
+        //hold a fair handoff (or leave a stale waker in the queue).
+        self.lock.deregister(&mut self.node);
+    }
+}
+
+impl<T: 'static> std::future::Future for OwnedLockFuture<T> {
+    type Output = LockResult<OwnedGuard<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
+        let this = self.get_mut();
+        let lock = this.lock.clone();
+        lock.poll_acquire(&mut this.node, cx).map(|guard| AtomicLockAsync::finish_lock_owned(&lock, guard))
     }
 }
 
@@ -140,7 +500,7 @@ pub struct LockWarnFuture<'a, T> {
 }
 
 impl<'a, T> std::future::Future for LockWarnFuture<'a, T> {
-    type Output = Guard<'a, T>;
+    type Output = LockResult<Guard<'a, T>>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
         let unchecked_mut = unsafe{self.get_unchecked_mut()};
@@ -156,6 +516,234 @@ impl<'a, T> std::future::Future for LockWarnFuture<'a, T> {
     }
 }
 
+/**
+A timer source that can produce a future sleeping until a given deadline.
+
+This lets [`AtomicLockAsync::lock_until`]/[`AtomicLockAsync::lock_timeout`] stay
+executor-agnostic rather than hard-depending on a particular async runtime's timer.
+*/
+pub trait Sleeper {
+    /**
+    The future returned by [`Self::sleep_until`].
+*/
+    type Sleep: std::future::Future<Output = ()>;
+
+    /**
+    Returns a future that resolves once `deadline` has passed.
+*/
+    fn sleep_until(deadline: Instant) -> Self::Sleep;
+}
+
+/**
+A future, produced by [`AtomicLockAsync::lock_timeout`]/[`AtomicLockAsync::lock_until`],
+which resolves to `None` if `S`'s deadline elapses before the lock is acquired.
+*/
+#[derive(Debug)]
+#[must_use]
+pub struct LockUntilFuture<'a, T, S: Sleeper> {
+    lock_future: LockFuture<'a, T>,
+    sleep: S::Sleep,
+}
+
+impl<'a, T, S: Sleeper> std::future::Future for LockUntilFuture<'a, T, S> {
+    type Output = Option<LockResult<Guard<'a, T>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
+        let unchecked_mut = unsafe{self.get_unchecked_mut()};
+        let lock_future = unsafe{Pin::new_unchecked(&mut unchecked_mut.lock_future)};
+        if let std::task::Poll::Ready(result) = lock_future.poll(cx) {
+            return std::task::Poll::Ready(Some(result));
+        }
+        //on a timeout, `lock_future` is dropped along with `self`, which deregisters its
+        //wakelist entry via `LockFuture`'s `Drop` impl. Critically, if we were the notified
+        //queue head, `deregister` wakes our successor too, so a timeout can't strand the
+        //rest of the queue with the lock free and nobody left to call `release`.
+        let sleep = unsafe{Pin::new_unchecked(&mut unchecked_mut.sleep)};
+        match sleep.poll(cx) {
+            std::task::Poll::Ready(()) => std::task::Poll::Ready(None),
+            std::task::Poll::Pending => std::task::Poll::Pending,
+        }
+    }
+}
+
+impl<T> AtomicLockAsync<T> {
+    /**
+    Locks the lock, giving up once `deadline` has passed.
+
+    `S` selects the timer source; pass whatever `Sleeper` implementation wraps your
+    executor's timer.
+    */
+    pub fn lock_until<S: Sleeper>(&self, deadline: Instant) -> LockUntilFuture<'_, T, S> {
+        LockUntilFuture { lock_future: self.lock(), sleep: S::sleep_until(deadline) }
+    }
+
+    /**
+    Locks the lock, giving up if it isn't acquired within `dur`.
+
+    `S` selects the timer source; pass whatever `Sleeper` implementation wraps your
+    executor's timer.
+    */
+    pub fn lock_timeout<S: Sleeper>(&self, dur: Duration) -> LockUntilFuture<'_, T, S> {
+        self.lock_until::<S>(Instant::now() + dur)
+    }
+}
+
+/**
+`AsyncRead`/`AsyncWrite` passthrough for a shared I/O object, inspired by [piper](https://docs.rs/piper)'s
+`Lock`.
+
+Each poll method parks on the same wakelist as [`AtomicLockAsync::lock`] until the lock is
+free, delegates exactly one poll to the inner `&mut T`, then releases the lock before
+returning — so the two halves of a split connection can each be wrapped once (via
+`&AtomicLockAsync<T>`, which is `Copy`) and handed to `futures::io::copy`-style combinators
+without either side manually juggling a [`Guard`] across `.await` points. A lock poisoned by
+a panicking holder is treated as if it weren't poisoned; I/O correctness doesn't depend on
+`T`'s invariants the way ordinary guarded access might.
+
+Caveat shared with any lock-backed `AsyncRead`/`AsyncWrite`: if a pending read or write is
+abandoned (polled once while contended, then never polled again) its wait-node stays parked
+in the shared wakelist until the next poll of that same direction, since there's no future
+of its own whose `Drop` could deregister it. Once that node reaches the head of the queue,
+`release` wakes only the node's stale waker, whose task no longer polls that direction, and nothing calls
+`deregister` to wake its successor; waiters queued behind it are therefore not woken until
+that same direction is polled again. If the node has waited longer than `FAIRNESS_THRESHOLD`
+it is also marked for a fair handoff, so other pollers queue behind it instead of taking the
+free lock.
+*/
+#[cfg(feature = "futures-io")]
+fn poll_read_via_lock<T>(
+    lock: &AtomicLockAsync<T>,
+    slot: &atomiclock_spinlock::Lock<Option<Arc<WaitNode>>>,
+    cx: &mut std::task::Context<'_>,
+    buf: &mut [u8],
+) -> Poll<std::io::Result<usize>>
+where
+    for<'t> &'t mut T: AsyncRead,
+{
+    let mut guard = match lock.poll_io_lock(slot, cx) {
+        Poll::Ready(result) => result.unwrap_or_else(PoisonError::into_inner),
+        Poll::Pending => return Poll::Pending,
+    };
+    let mut io_ref: &mut T = guard.as_mut();
+    Pin::new(&mut io_ref).poll_read(cx, buf)
+}
+
+#[cfg(feature = "futures-io")]
+fn poll_write_via_lock<T>(
+    lock: &AtomicLockAsync<T>,
+    slot: &atomiclock_spinlock::Lock<Option<Arc<WaitNode>>>,
+    cx: &mut std::task::Context<'_>,
+    buf: &[u8],
+) -> Poll<std::io::Result<usize>>
+where
+    for<'t> &'t mut T: AsyncWrite,
+{
+    let mut guard = match lock.poll_io_lock(slot, cx) {
+        Poll::Ready(result) => result.unwrap_or_else(PoisonError::into_inner),
+        Poll::Pending => return Poll::Pending,
+    };
+    let mut io_ref: &mut T = guard.as_mut();
+    Pin::new(&mut io_ref).poll_write(cx, buf)
+}
+
+#[cfg(feature = "futures-io")]
+fn poll_flush_via_lock<T>(
+    lock: &AtomicLockAsync<T>,
+    slot: &atomiclock_spinlock::Lock<Option<Arc<WaitNode>>>,
+    cx: &mut std::task::Context<'_>,
+) -> Poll<std::io::Result<()>>
+where
+    for<'t> &'t mut T: AsyncWrite,
+{
+    let mut guard = match lock.poll_io_lock(slot, cx) {
+        Poll::Ready(result) => result.unwrap_or_else(PoisonError::into_inner),
+        Poll::Pending => return Poll::Pending,
+    };
+    let mut io_ref: &mut T = guard.as_mut();
+    Pin::new(&mut io_ref).poll_flush(cx)
+}
+
+#[cfg(feature = "futures-io")]
+fn poll_close_via_lock<T>(
+    lock: &AtomicLockAsync<T>,
+    slot: &atomiclock_spinlock::Lock<Option<Arc<WaitNode>>>,
+    cx: &mut std::task::Context<'_>,
+) -> Poll<std::io::Result<()>>
+where
+    for<'t> &'t mut T: AsyncWrite,
+{
+    let mut guard = match lock.poll_io_lock(slot, cx) {
+        Poll::Ready(result) => result.unwrap_or_else(PoisonError::into_inner),
+        Poll::Pending => return Poll::Pending,
+    };
+    let mut io_ref: &mut T = guard.as_mut();
+    Pin::new(&mut io_ref).poll_close(cx)
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> AsyncRead for AtomicLockAsync<T>
+where
+    for<'t> &'t mut T: AsyncRead,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        poll_read_via_lock(this, &this.io_read, cx, buf)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> AsyncWrite for AtomicLockAsync<T>
+where
+    for<'t> &'t mut T: AsyncWrite,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        poll_write_via_lock(this, &this.io_write, cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        poll_flush_via_lock(this, &this.io_write, cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        poll_close_via_lock(this, &this.io_write, cx)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> AsyncRead for &AtomicLockAsync<T>
+where
+    for<'t> &'t mut T: AsyncRead,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let this = *self.get_mut();
+        poll_read_via_lock(this, &this.io_read, cx, buf)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> AsyncWrite for &AtomicLockAsync<T>
+where
+    for<'t> &'t mut T: AsyncWrite,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        let this = *self.get_mut();
+        poll_write_via_lock(this, &this.io_write, cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = *self.get_mut();
+        poll_flush_via_lock(this, &this.io_write, cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = *self.get_mut();
+        poll_close_via_lock(this, &this.io_write, cx)
+    }
+}
+
     /*
 boilerplate notes.
 
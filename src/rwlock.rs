@@ -0,0 +1,420 @@
+//SPDX-License-Identifier: MIT OR Apache-2.0
+
+/*! Provides a non-blocking reader-writer lock.
+
+Like [`crate::AtomicLockAsync`], but distinguishes shared read access from exclusive
+write access, so read-mostly shared state doesn't have to serialize behind a single
+mutex.
+ */
+
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use atomic_waker::AtomicWaker;
+use logwise::perfwarn_begin;
+
+/**
+The high bit of [`RwLock`]'s state word; set while a writer holds the lock. The
+remaining bits count active readers.
+*/
+const WRITER: usize = 1 << (usize::BITS - 1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WaitKind {
+    Read,
+    Write,
+}
+
+/**
+An entry in the FIFO wakelist, analogous to `AtomicLockAsync`'s internal wait node but
+also recording whether the waiter wants shared or exclusive access.
+*/
+#[derive(Debug)]
+struct WaitNode {
+    kind: WaitKind,
+    waker: AtomicWaker,
+}
+
+#[derive(Debug)]
+pub struct RwLock<T> {
+    state: AtomicUsize,
+    value: UnsafeCell<T>,
+    wakelist: atomiclock_spinlock::Lock<VecDeque<Arc<WaitNode>>>,
+}
+
+unsafe impl<T: Send> Send for RwLock<T> {}
+unsafe impl<T: Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    /**
+    Creates a new lock.
+*/
+    pub const fn new(t: T) -> Self {
+        RwLock {
+            state: AtomicUsize::new(0),
+            value: UnsafeCell::new(t),
+            wakelist: atomiclock_spinlock::Lock::new(VecDeque::new()),
+        }
+    }
+
+    /**
+    Whether a writer is currently queued. While true, new readers must not take the
+    opportunistic fast path, so a steady stream of readers can't starve the writer.
+*/
+    fn writer_queued(&self) -> bool {
+        self.wakelist.spin_lock_warn().iter().any(|n| n.kind == WaitKind::Write)
+    }
+
+    fn try_read_raw(&self) -> Option<RwLockReadGuard<'_, T>> {
+        let mut current = self.state.load(Ordering::Acquire);
+        loop {
+            if current & WRITER != 0 {
+                return None;
+            }
+            match self.state.compare_exchange_weak(current, current + 1, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return Some(RwLockReadGuard { lock: self }),
+                Err(observed) => current = observed,
+            }
+        }
+    }
+
+    fn try_write_raw(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        self.state.compare_exchange(0, WRITER, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| RwLockWriteGuard { lock: self })
+    }
+
+    /**
+    Acquires a read lock if it is available, without waiting. Returns `None` if a writer
+    holds the lock or is queued for it.
+*/
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        if self.writer_queued() {
+            return None;
+        }
+        self.try_read_raw()
+    }
+
+    /**
+    Acquires a write lock if it is available.
+*/
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        self.try_write_raw()
+    }
+
+    /**
+    Locks this lock with shared read access.
+*/
+    pub fn read(&self) -> RwLockReadFuture<'_, T> {
+        RwLockReadFuture { lock: self, node: None }
+    }
+
+    /**
+    Like `read`, but with a performance warning.
+
+    Use this to indicate that the use of read is suspicious.
+    */
+    pub fn read_warn(&self) -> RwLockReadWarnFuture<'_, T> {
+        RwLockReadWarnFuture { underlying_future: self.read(), perfwarn_interval: None }
+    }
+
+    /**
+    Locks this lock with exclusive write access.
+*/
+    pub fn write(&self) -> RwLockWriteFuture<'_, T> {
+        RwLockWriteFuture { lock: self, node: None }
+    }
+
+    /**
+    Like `write`, but with a performance warning.
+
+    Use this to indicate that the use of write is suspicious.
+    */
+    pub fn write_warn(&self) -> RwLockWriteWarnFuture<'_, T> {
+        RwLockWriteWarnFuture { underlying_future: self.write(), perfwarn_interval: None }
+    }
+
+    /**
+    Consumes the lock, returning the inner value.
+*/
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /**
+    Wakes a batch of consecutive readers at the front of `queue`, or else a single writer,
+    stopping as soon as further progress would require exclusive access.
+    */
+    fn wake_front_batch(queue: &VecDeque<Arc<WaitNode>>) {
+        let mut woke_any_reader = false;
+        for node in queue.iter() {
+            match node.kind {
+                WaitKind::Read => {
+                    node.waker.wake();
+                    woke_any_reader = true;
+                }
+                WaitKind::Write => {
+                    if !woke_any_reader {
+                        node.waker.wake();
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    /**
+    Removes `node`'s queue entry, if any. Called when an `RwLockReadFuture`/`RwLockWriteFuture`
+    is dropped before acquiring.
+
+    If the removed entry was the head of the queue, its successor(s) are woken here too:
+    `release_read`/`release_write` only ever wake the *current* head (batch), so a waiter
+    cancelled after being woken but before re-polling — while the lock is already free —
+    would otherwise strand everyone behind it forever.
+    */
+    fn deregister(&self, node: &mut Option<Arc<WaitNode>>) {
+        if let Some(n) = node.take() {
+            let mut queue = self.wakelist.spin_lock_warn();
+            let was_front = queue.front().is_some_and(|head| Arc::ptr_eq(head, &n));
+            queue.retain(|x| !Arc::ptr_eq(x, &n));
+            if was_front {
+                Self::wake_front_batch(&queue);
+            }
+        }
+    }
+
+    fn release_read(&self) {
+        let previous = self.state.fetch_sub(1, Ordering::Release);
+        if previous == 1 {
+            //we were the last reader; wake the next batch of the queue (consecutive
+            //readers, or else a single writer), mirroring `release_write`.
+            let queue = self.wakelist.spin_lock_warn();
+            Self::wake_front_batch(&queue);
+        }
+    }
+
+    fn release_write(&self) {
+        self.state.store(0, Ordering::Release);
+        let queue = self.wakelist.spin_lock_warn();
+        Self::wake_front_batch(&queue);
+    }
+}
+
+/**
+A guard providing shared read access to the value inside an [`RwLock`].
+*/
+#[derive(Debug)]
+pub struct RwLockReadGuard<'a, T> {
+    lock: &'a RwLock<T>,
+}
+
+unsafe impl<T: Sync> Sync for RwLockReadGuard<'_, T> {}
+
+impl<T> Drop for RwLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.release_read();
+    }
+}
+
+impl<T> std::ops::Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+/**
+A guard providing exclusive write access to the value inside an [`RwLock`].
+*/
+#[derive(Debug)]
+pub struct RwLockWriteGuard<'a, T> {
+    lock: &'a RwLock<T>,
+}
+
+unsafe impl<T: Sync> Sync for RwLockWriteGuard<'_, T> {}
+
+impl<T> Drop for RwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.release_write();
+    }
+}
+
+impl<T> std::ops::Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> std::ops::DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+/**
+A future, produced by [`RwLock::read`], which resolves to an [`RwLockReadGuard`].
+*/
+#[derive(Debug)]
+#[must_use]
+pub struct RwLockReadFuture<'a, T> {
+    lock: &'a RwLock<T>,
+    node: Option<Arc<WaitNode>>,
+}
+
+impl<T> Drop for RwLockReadFuture<'_, T> {
+    fn drop(&mut self) {
+        self.lock.deregister(&mut self.node);
+    }
+}
+
+impl<'a, T> std::future::Future for RwLockReadFuture<'a, T> {
+    type Output = RwLockReadGuard<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.node {
+            Some(ref node) => {
+                node.waker.register(cx.waker());
+                if let Some(guard) = this.lock.try_read_raw() {
+                    let node = this.node.take().unwrap();
+                    this.lock.wakelist.spin_lock_warn().retain(|x| !Arc::ptr_eq(x, &node));
+                    Poll::Ready(guard)
+                } else {
+                    Poll::Pending
+                }
+            }
+            None => {
+                //opportunistic fast path, unless a writer is already queued (to avoid
+                //starving it).
+                if !this.lock.writer_queued() {
+                    if let Some(guard) = this.lock.try_read_raw() {
+                        return Poll::Ready(guard);
+                    }
+                }
+                let node = Arc::new(WaitNode { kind: WaitKind::Read, waker: AtomicWaker::new() });
+                node.waker.register(cx.waker());
+                this.lock.wakelist.spin_lock_warn().push_back(node.clone());
+                this.node = Some(node);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/**
+Like [`RwLockReadFuture`], but with a performance warning.
+*/
+#[derive(Debug)]
+#[must_use]
+pub struct RwLockReadWarnFuture<'a, T> {
+    underlying_future: RwLockReadFuture<'a, T>,
+    perfwarn_interval: Option<logwise::interval::PerfwarnInterval>,
+}
+
+impl<'a, T> std::future::Future for RwLockReadWarnFuture<'a, T> {
+    type Output = RwLockReadGuard<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let unchecked_mut = unsafe { self.get_unchecked_mut() };
+        if let None = unchecked_mut.perfwarn_interval {
+            unchecked_mut.perfwarn_interval = Some(perfwarn_begin!("RwLock::read"));
+        }
+        let underlying_future = unsafe { Pin::new_unchecked(&mut unchecked_mut.underlying_future) };
+        let r = underlying_future.poll(cx);
+        if let Poll::Ready(_) = r {
+            unchecked_mut.perfwarn_interval.take();
+        }
+        r
+    }
+}
+
+/**
+A future, produced by [`RwLock::write`], which resolves to an [`RwLockWriteGuard`].
+*/
+#[derive(Debug)]
+#[must_use]
+pub struct RwLockWriteFuture<'a, T> {
+    lock: &'a RwLock<T>,
+    node: Option<Arc<WaitNode>>,
+}
+
+impl<T> Drop for RwLockWriteFuture<'_, T> {
+    fn drop(&mut self) {
+        self.lock.deregister(&mut self.node);
+    }
+}
+
+impl<'a, T> std::future::Future for RwLockWriteFuture<'a, T> {
+    type Output = RwLockWriteGuard<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.node {
+            Some(ref node) => {
+                node.waker.register(cx.waker());
+                if let Some(guard) = this.lock.try_write_raw() {
+                    let node = this.node.take().unwrap();
+                    this.lock.wakelist.spin_lock_warn().retain(|x| !Arc::ptr_eq(x, &node));
+                    Poll::Ready(guard)
+                } else {
+                    Poll::Pending
+                }
+            }
+            None => {
+                if let Some(guard) = this.lock.try_write_raw() {
+                    return Poll::Ready(guard);
+                }
+                let node = Arc::new(WaitNode { kind: WaitKind::Write, waker: AtomicWaker::new() });
+                node.waker.register(cx.waker());
+                this.lock.wakelist.spin_lock_warn().push_back(node.clone());
+                this.node = Some(node);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/**
+Like [`RwLockWriteFuture`], but with a performance warning.
+*/
+#[derive(Debug)]
+#[must_use]
+pub struct RwLockWriteWarnFuture<'a, T> {
+    underlying_future: RwLockWriteFuture<'a, T>,
+    perfwarn_interval: Option<logwise::interval::PerfwarnInterval>,
+}
+
+impl<'a, T> std::future::Future for RwLockWriteWarnFuture<'a, T> {
+    type Output = RwLockWriteGuard<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let unchecked_mut = unsafe { self.get_unchecked_mut() };
+        if let None = unchecked_mut.perfwarn_interval {
+            unchecked_mut.perfwarn_interval = Some(perfwarn_begin!("RwLock::write"));
+        }
+        let underlying_future = unsafe { Pin::new_unchecked(&mut unchecked_mut.underlying_future) };
+        let r = underlying_future.poll(cx);
+        if let Poll::Ready(_) = r {
+            unchecked_mut.perfwarn_interval.take();
+        }
+        r
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        RwLock::new(T::default())
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(t: T) -> Self {
+        RwLock::new(t)
+    }
+}